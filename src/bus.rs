@@ -0,0 +1,54 @@
+/// The address space a `CPU` executes against.
+///
+/// Implementing this trait (instead of hard-coding a flat array inside the
+/// CPU) lets callers plug in whatever memory map they need -- a simple flat
+/// array for unit tests, or eventually a real NES bus that routes reads and
+/// writes to PPU/APU registers, mirrored RAM, and mapped cartridge ROM.
+pub trait Bus {
+    fn mem_read(&self, addr: u16) -> u8;
+    fn mem_write(&mut self, addr: u16, data: u8);
+
+    fn mem_read_u16(&self, addr: u16) -> u16 {
+        let low = self.mem_read(addr) as u16;
+        let high = self.mem_read(addr.wrapping_add(1)) as u16;
+
+        (high << 8) | low
+    }
+
+    fn mem_write_u16(&mut self, addr: u16, data: u16) {
+        let high = (data >> 8) as u8;
+        let low = (data & 0xFF) as u8;
+
+        self.mem_write(addr, low);
+        self.mem_write(addr.wrapping_add(1), high);
+    }
+}
+
+/// The simplest possible `Bus`: a flat 64KB address space with no mapping.
+pub struct Memory {
+    space: [u8; 0x10000],
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory {
+            space: [0; 0x10000],
+        }
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Memory::new()
+    }
+}
+
+impl Bus for Memory {
+    fn mem_read(&self, addr: u16) -> u8 {
+        self.space[addr as usize]
+    }
+
+    fn mem_write(&mut self, addr: u16, data: u8) {
+        self.space[addr as usize] = data;
+    }
+}
@@ -0,0 +1,88 @@
+//! A correctness harness built around Klaus Dormann's 6502 functional test
+//! ROM (<https://github.com/Klaus2m5/6502_65C02_functional_tests>), which
+//! exercises every legal instruction and addressing mode and then loops
+//! forever at a fixed "success" address once every test has passed.
+//!
+//! The binary isn't checked into this repository -- see the `#[ignore]`d
+//! test below for how to point the harness at a local copy.
+
+use crate::bus::Bus;
+use crate::cpu::CPU;
+
+/// Where the functional test ROM expects to be loaded.
+const LOAD_ADDRESS: u16 = 0x0400;
+/// The address the ROM jumps to (and loops on forever) once every test has
+/// passed. Anywhere else is a failure trapped mid-test.
+const SUCCESS_ADDRESS: u16 = 0x3469;
+
+/// Loads `binary` at `origin`, sets the PC there, and single-steps `cpu`
+/// until it traps -- an instruction whose target is its own address, so the
+/// PC stops advancing between steps. Returns the address it trapped at.
+pub fn run_until_trap<M: Bus>(cpu: &mut CPU<M>, binary: &[u8], origin: u16) -> u16 {
+    for (offset, byte) in binary.iter().enumerate() {
+        cpu.bus.mem_write(origin.wrapping_add(offset as u16), *byte);
+    }
+    cpu.program_counter = origin;
+
+    loop {
+        let pc_before = cpu.program_counter;
+        cpu.step();
+        if cpu.program_counter == pc_before {
+            return pc_before;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bus::Memory;
+    use crate::cpu::Variant;
+    use std::env;
+    use std::fs;
+
+    /// Exercises `run_until_trap` itself, independent of the external ROM:
+    /// a few ordinary instructions followed by a `JMP` back onto its own
+    /// address should run to completion and trap exactly there.
+    #[test]
+    fn test_run_until_trap_detects_a_self_jump() {
+        let mut cpu = CPU::new(Memory::new(), Variant::Nmos);
+        // LDA #$01; NOP; JMP $0403 (jumps to itself)
+        let program = [0xa9, 0x01, 0xea, 0x4c, 0x03, 0x04];
+
+        let trap_address = run_until_trap(&mut cpu, &program, LOAD_ADDRESS);
+
+        assert_eq!(trap_address, LOAD_ADDRESS + 3);
+        assert_eq!(cpu.register_a, 0x01);
+    }
+
+    /// Ignored by default: needs a local copy of the Klaus Dormann 6502
+    /// functional test binary. Get one from
+    /// https://github.com/Klaus2m5/6502_65C02_functional_tests and run with:
+    ///
+    /// ```sh
+    /// KLAUS_DORMANN_ROM=/path/to/6502_functional_test.bin \
+    ///     cargo test --workspace -- --ignored klaus_dormann
+    /// ```
+    ///
+    /// This sandbox has no network access to fetch that binary, so this
+    /// particular test has not been run against the real ROM; the
+    /// self-jump test above is what actually exercises `run_until_trap`
+    /// here.
+    #[test]
+    #[ignore]
+    fn klaus_dormann_functional_test() {
+        let path = env::var("KLAUS_DORMANN_ROM")
+            .expect("set KLAUS_DORMANN_ROM to the path of the test binary");
+        let binary = fs::read(path).expect("failed to read functional test ROM");
+
+        let mut cpu = CPU::new(Memory::new(), Variant::Nmos);
+        let trap_address = run_until_trap(&mut cpu, &binary, LOAD_ADDRESS);
+
+        assert_eq!(
+            trap_address, SUCCESS_ADDRESS,
+            "functional test trapped at {:#06x}, expected the success address {:#06x}",
+            trap_address, SUCCESS_ADDRESS
+        );
+    }
+}
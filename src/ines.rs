@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::bus::Bus;
+
+const NES_TAG: [u8; 4] = [0x4E, 0x45, 0x53, 0x1A];
+
+pub const PRG_ROM_PAGE_SIZE: usize = 16 * 1024;
+pub const CHR_ROM_PAGE_SIZE: usize = 8 * 1024;
+const PRG_RAM_SIZE: usize = 0x2000; // 8KB, mapped at 0x6000-0x7FFF
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirroring {
+    Vertical,
+    Horizontal,
+    FourScreen,
+}
+
+/// A parsed iNES (`.nes`) file: PRG/CHR ROM banks plus the header fields
+/// that decide how a `Cartridge` maps them into the CPU's address space.
+pub struct Rom {
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>,
+    pub mapper: u8,
+    pub mirroring: Mirroring,
+    pub battery: bool,
+}
+
+impl Rom {
+    pub fn new(raw: &[u8]) -> Result<Rom, String> {
+        if raw.len() < 16 || raw[0..4] != NES_TAG {
+            return Err("File is not in iNES file format".to_string());
+        }
+
+        let mapper = (raw[7] & 0b1111_0000) | (raw[6] >> 4);
+
+        let ines_ver = (raw[7] >> 2) & 0b11;
+        if ines_ver != 0 {
+            return Err("NES2.0 format is not supported".to_string());
+        }
+
+        let four_screen = raw[6] & 0b1000 != 0;
+        let vertical_mirroring = raw[6] & 0b1 != 0;
+        let mirroring = match (four_screen, vertical_mirroring) {
+            (true, _) => Mirroring::FourScreen,
+            (false, true) => Mirroring::Vertical,
+            (false, false) => Mirroring::Horizontal,
+        };
+
+        let battery = raw[6] & 0b10 != 0;
+
+        let skip_trainer = raw[6] & 0b100 != 0;
+
+        let prg_rom_size = raw[4] as usize * PRG_ROM_PAGE_SIZE;
+        let chr_rom_size = raw[5] as usize * CHR_ROM_PAGE_SIZE;
+
+        let prg_rom_start = 16 + if skip_trainer { 512 } else { 0 };
+        let chr_rom_start = prg_rom_start + prg_rom_size;
+
+        if raw.len() < chr_rom_start + chr_rom_size {
+            return Err("iNES header doesn't match the file size".to_string());
+        }
+
+        Ok(Rom {
+            prg_rom: raw[prg_rom_start..(prg_rom_start + prg_rom_size)].to_vec(),
+            chr_rom: raw[chr_rom_start..(chr_rom_start + chr_rom_size)].to_vec(),
+            mapper,
+            mirroring,
+            battery,
+        })
+    }
+}
+
+/// A `Bus` that maps a `Rom`'s PRG-ROM into `0x8000-0xFFFF` (mirroring a
+/// 16KB bank across both halves) and its PRG-RAM into `0x6000-0x7FFF`.
+///
+/// Only mapper 0 (NROM) addressing is implemented; the mapper number is
+/// still parsed and kept on the `Rom` for when bank-switched mappers land.
+pub struct Cartridge {
+    rom: Rom,
+    prg_ram: [u8; PRG_RAM_SIZE],
+    sav_path: Option<PathBuf>,
+}
+
+impl Cartridge {
+    pub fn new(rom: Rom) -> Self {
+        Cartridge {
+            rom,
+            prg_ram: [0; PRG_RAM_SIZE],
+            sav_path: None,
+        }
+    }
+
+    /// Loads a `.nes` file from disk. If the cartridge has a battery, also
+    /// restores PRG-RAM from the `.sav` file next to it (if one exists) and
+    /// remembers that path for later `save_ram` calls.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let raw = fs::read(path.as_ref()).map_err(|e| e.to_string())?;
+        let rom = Rom::new(&raw)?;
+        let mut cartridge = Cartridge::new(rom);
+
+        if cartridge.rom.battery {
+            let sav_path = path.as_ref().with_extension("sav");
+            if sav_path.exists() {
+                cartridge.load_ram(&sav_path)?;
+            }
+            cartridge.sav_path = Some(sav_path);
+        }
+
+        Ok(cartridge)
+    }
+
+    /// Persists PRG-RAM to the cartridge's `.sav` path. A no-op for
+    /// cartridges without a battery.
+    pub fn save_ram(&self) -> Result<(), String> {
+        match &self.sav_path {
+            Some(path) => fs::write(path, self.prg_ram).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_ram(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let data = fs::read(path).map_err(|e| e.to_string())?;
+        let len = data.len().min(self.prg_ram.len());
+        self.prg_ram[..len].copy_from_slice(&data[..len]);
+        Ok(())
+    }
+
+    fn read_prg_rom(&self, addr: u16) -> u8 {
+        let mut addr = addr - 0x8000;
+        if self.rom.prg_rom.len() == PRG_ROM_PAGE_SIZE && addr >= PRG_ROM_PAGE_SIZE as u16 {
+            // A single 16KB bank is mirrored across both ROM halves.
+            addr %= PRG_ROM_PAGE_SIZE as u16;
+        }
+        self.rom.prg_rom[addr as usize]
+    }
+}
+
+impl Bus for Cartridge {
+    fn mem_read(&self, addr: u16) -> u8 {
+        match addr {
+            0x6000..=0x7FFF => self.prg_ram[(addr - 0x6000) as usize],
+            0x8000..=0xFFFF => self.read_prg_rom(addr),
+            _ => 0,
+        }
+    }
+
+    fn mem_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x6000..=0x7FFF => self.prg_ram[(addr - 0x6000) as usize] = data,
+            0x8000..=0xFFFF => {} // ROM is read-only
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    fn minimal_ines_bytes(battery: bool) -> Vec<u8> {
+        let mut raw = vec![0u8; 16 + PRG_ROM_PAGE_SIZE + CHR_ROM_PAGE_SIZE];
+        raw[0..4].copy_from_slice(&NES_TAG);
+        raw[4] = 1; // 1 PRG-ROM page
+        raw[5] = 1; // 1 CHR-ROM page
+        raw[6] = if battery { 0b0000_0010 } else { 0 };
+        raw[7] = 0;
+
+        raw
+    }
+
+    #[test]
+    fn test_rom_rejects_non_ines_file() {
+        let raw = vec![0u8; 32];
+
+        assert!(Rom::new(&raw).is_err());
+    }
+
+    #[test]
+    fn test_rom_parses_minimal_header() {
+        let raw = minimal_ines_bytes(false);
+
+        let rom = Rom::new(&raw).expect("valid iNES file should parse");
+
+        assert_eq!(rom.prg_rom.len(), PRG_ROM_PAGE_SIZE);
+        assert_eq!(rom.chr_rom.len(), CHR_ROM_PAGE_SIZE);
+        assert_eq!(rom.mapper, 0);
+        assert_eq!(rom.mirroring, Mirroring::Horizontal);
+        assert!(!rom.battery);
+    }
+
+    #[test]
+    fn test_cartridge_maps_prg_ram_and_mirrors_16kb_prg_rom() {
+        let rom = Rom::new(&minimal_ines_bytes(false)).unwrap();
+        let mut cartridge = Cartridge::new(rom);
+
+        cartridge.mem_write(0x6000, 0xab);
+        assert_eq!(cartridge.mem_read(0x6000), 0xab);
+
+        // A single 16KB PRG-ROM bank is mirrored into both ROM halves.
+        assert_eq!(cartridge.mem_read(0x8000), cartridge.mem_read(0xc000));
+    }
+
+    #[test]
+    fn test_save_ram_round_trips_through_the_sav_file() {
+        let path = std::env::temp_dir().join("nes_emulator_test_save_ram_round_trip.nes");
+        let sav_path = path.with_extension("sav");
+        fs::write(&path, minimal_ines_bytes(true)).unwrap();
+        let _ = fs::remove_file(&sav_path);
+
+        let mut cartridge = Cartridge::from_file(&path).unwrap();
+        cartridge.mem_write(0x6000, 0x42);
+        cartridge.save_ram().unwrap();
+
+        let reloaded = Cartridge::from_file(&path).unwrap();
+        assert_eq!(reloaded.mem_read(0x6000), 0x42);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&sav_path).unwrap();
+    }
+}
@@ -1,4 +1,7 @@
+pub mod bus;
 pub mod cpu;
+pub mod functional_test;
+pub mod ines;
 pub mod opcodes;
 
 #[macro_use]
@@ -7,8 +10,23 @@ extern crate lazy_static;
 #[macro_use]
 extern crate bitflags;
 
-use cpu::CPU;
+use std::env;
+
+use bus::Memory;
+use cpu::{Variant, CPU};
+use ines::Cartridge;
 
 fn main() {
-    let cpu = CPU::new();
+    match env::args().nth(1) {
+        Some(rom_path) => {
+            let cartridge = Cartridge::from_file(&rom_path).expect("failed to load ROM");
+            let mut cpu = CPU::new(cartridge, Variant::Nmos);
+
+            cpu.reset();
+            cpu.run();
+        }
+        None => {
+            let cpu = CPU::new(Memory::new(), Variant::Nmos);
+        }
+    }
 }